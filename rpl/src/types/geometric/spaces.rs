@@ -1,92 +1,582 @@
-use itertools::{multizip, Itertools};
+use bitflags::bitflags;
+use itertools::multizip;
+use rand::Rng;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+/// Which invariants `StateSpace::sanity_checks` should verify; combine with `|`.
+bitflags! {
+    pub struct SanityFlags: u32 {
+        /// Two distinct sampled states must have strictly positive distance.
+        const DISTANCE_DIFFERENT_STATES = 1 << 0;
+        /// `distance(a, b)` must equal `distance(b, a)` within epsilon.
+        const DISTANCE_SYMMETRIC = 1 << 1;
+        /// `distance(a, b)` must not exceed `count_segments_between(a, b) * segment_length`.
+        const DISTANCE_BOUND = 1 << 2;
+        /// `interpolate(a, b, 0) == a`, `interpolate(a, b, 1) == b`, and `distance(a,
+        /// interpolate(a, b, t)) == t * distance(a, b)` for several `t`.
+        const INTERPOLATION = 1 << 3;
+        /// `distance(a, c) <= distance(a, b) + distance(b, c)` within epsilon.
+        const TRIANGLE_INEQUALITY = 1 << 4;
+        /// Sampled states, and states that have had bounds enforced, satisfy bounds.
+        const RESPECT_BOUNDS = 1 << 5;
+        /// Enforcing bounds on an already-valid state must not change it.
+        const ENFORCE_BOUNDS_NO_OP = 1 << 6;
+    }
+}
+
+/// The reason a `StateSpace::sanity_checks` run failed, identifying the specific invariant that
+/// didn't hold and the values that violated it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SanityError {
+    DistanceNotPositiveForDistinctStates,
+    DistanceNotSymmetric { forward: f64, backward: f64 },
+    DistanceExceedsSegmentBound { distance: f64, bound: f64 },
+    InterpolationEndpointMismatch,
+    InterpolationDistanceMismatch { expected: f64, actual: f64 },
+    TriangleInequalityViolated { direct: f64, via_intermediate: f64 },
+    SampledStateOutOfBounds,
+    EnforceBoundsNotNoOp,
+}
+
+/// Projects states from a (typically high-dimensional) `StateSpace` down to a low-dimensional
+/// coordinate that grid-based planners (KPIECE and friends) discretize using `cell_sizes` to
+/// estimate how much of the space they've covered.
+pub trait ProjectionEvaluator {
+    /// The dimension of the projected coordinate.
+    fn dimension(&self) -> usize;
+
+    /// Writes the projection of `state` into `coord`, which must be `dimension()` long.
+    fn project(&self, state: &State, coord: &mut [f64]);
+
+    /// The size of one grid cell along each projected dimension.
+    fn cell_sizes(&self) -> &[f64];
+}
+
+/// A named set of `ProjectionEvaluator`s for a `StateSpace`, with one of them designated the
+/// default (the first one registered, as in OMPL). Spaces hold one of these and delegate
+/// `StateSpace::{register,get,get_default}_projection` to it.
+#[derive(Default)]
+pub struct ProjectionRegistry {
+    projections: HashMap<String, Rc<dyn ProjectionEvaluator>>,
+    default_name: Option<String>,
+}
+
+impl ProjectionRegistry {
+    pub fn new() -> Self {
+        Self {
+            projections: HashMap::new(),
+            default_name: None,
+        }
+    }
+
+    pub fn register(&mut self, name: String, projection: Rc<dyn ProjectionEvaluator>) {
+        if self.default_name.is_none() {
+            self.default_name = Some(name.clone());
+        }
+        self.projections.insert(name, projection);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Rc<dyn ProjectionEvaluator>> {
+        self.projections.get(name)
+    }
+
+    pub fn get_default(&self) -> Option<&Rc<dyn ProjectionEvaluator>> {
+        self.default_name.as_ref().and_then(|name| self.projections.get(name))
+    }
+}
+
+/// A projection onto a lower-dimensional space via a fixed linear map, one row of `matrix` per
+/// projected dimension. Used for `RealVectorStateSpace`'s auto-generated default projection.
+pub struct LinearProjection {
+    matrix: Vec<Vec<f64>>,
+    cell_sizes: Vec<f64>,
+}
+
+impl ProjectionEvaluator for LinearProjection {
+    fn dimension(&self) -> usize {
+        self.matrix.len()
+    }
+
+    fn project(&self, state: &State, coord: &mut [f64]) {
+        let values = &state.as_real_vector().values;
+        for (row, out) in self.matrix.iter().zip(coord.iter_mut()) {
+            *out = row.iter().zip(values).map(|(m, v)| m * v).sum();
+        }
+    }
+
+    fn cell_sizes(&self) -> &[f64] {
+        &self.cell_sizes
+    }
+}
+
+/// Builds the default projection for a `RealVectorStateSpace`: the identity when the space is
+/// already low-dimensional, otherwise a random orthonormal linear map down to 2 dimensions - the
+/// same fallback OMPL's `RealVectorStateSpace` uses so KPIECE-style planners have something to
+/// project onto out of the box.
+fn default_real_vector_projection(dimension: usize, bounds: &RealVectorBounds) -> LinearProjection {
+    let projected_dims = dimension.min(2);
+    let matrix = if dimension <= 2 {
+        (0..dimension)
+            .map(|i| (0..dimension).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+            .collect()
+    } else {
+        random_orthonormal_rows(projected_dims, dimension)
+    };
+
+    let cell_sizes = (0..projected_dims)
+        .map(|i| {
+            let extent = bounds.high[i] - bounds.low[i];
+            if extent > 0.0 {
+                extent / 20.0
+            } else {
+                1.0
+            }
+        })
+        .collect();
+
+    LinearProjection { matrix, cell_sizes }
+}
+
+/// `rows` random vectors of length `cols`, orthonormalized with Gram-Schmidt.
+fn random_orthonormal_rows(rows: usize, cols: usize) -> Vec<Vec<f64>> {
+    let mut rng = rand::thread_rng();
+    let mut basis: Vec<Vec<f64>> = Vec::with_capacity(rows);
+    for _ in 0..rows {
+        let mut v: Vec<f64> = (0..cols).map(|_| rng.gen_range(-1.0..1.0)).collect();
+        for prior in &basis {
+            let dot: f64 = v.iter().zip(prior).map(|(a, b)| a * b).sum();
+            for (x, p) in v.iter_mut().zip(prior) {
+                *x -= dot * p;
+            }
+        }
+        let norm = v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        for x in v.iter_mut() {
+            *x /= norm;
+        }
+        basis.push(v);
+    }
+    basis
+}
+
+/// The default projection of a `CompoundStateSpace`: the default projection of every component
+/// that has one, concatenated in declaration order. Components without a default projection of
+/// their own are skipped rather than making the whole thing unavailable; `None` is only returned
+/// when no component has a default to contribute.
+pub struct CompoundProjection {
+    /// `(index into the CompoundState's components, that component's projection)`.
+    components: Vec<(usize, Rc<dyn ProjectionEvaluator>)>,
+    cell_sizes: Vec<f64>,
+}
+
+impl ProjectionEvaluator for CompoundProjection {
+    fn dimension(&self) -> usize {
+        self.components.iter().map(|(_, p)| p.dimension()).sum()
+    }
+
+    fn project(&self, state: &State, coord: &mut [f64]) {
+        let sub_states = &state.as_compound().components;
+        let mut offset = 0;
+        for (index, projection) in &self.components {
+            let dimension = projection.dimension();
+            projection.project(&sub_states[*index], &mut coord[offset..offset + dimension]);
+            offset += dimension;
+        }
+    }
+
+    fn cell_sizes(&self) -> &[f64] {
+        &self.cell_sizes
+    }
+}
+
+/// Builds a `CompoundProjection` out of each component's own default projection (or `None` if
+/// no component has one), in declaration order. Shared between `CompoundStateSpace::new` and
+/// `rpl_derive`'s generated `StateSpace` impls, so both ways of building a compound space get
+/// the same out-of-the-box default projection.
+pub fn concatenate_default_projections<'a>(
+    projections: impl IntoIterator<Item = Option<&'a Rc<dyn ProjectionEvaluator>>>,
+) -> Option<CompoundProjection> {
+    let sub_projections: Vec<(usize, Rc<dyn ProjectionEvaluator>)> = projections
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, projection)| projection.map(|p| (index, p.clone())))
+        .collect();
+
+    if sub_projections.is_empty() {
+        return None;
+    }
+
+    let cell_sizes = sub_projections.iter().flat_map(|(_, p)| p.cell_sizes().to_vec()).collect();
+    Some(CompoundProjection { components: sub_projections, cell_sizes })
+}
 
 /// Geometric State Spaces:
 /// This module defines the general state space and state interfaces, as well as implementations for
 /// several important/common geometric state spaces
 
-/// A trait representing a generic state in a geometric state space. Implementers are expected to
-/// hold a reference to the state space instance for a given state; that's about the only
-/// requirement. This interface ties a state type to its corresponding state space. States must also
-/// be copyable
-trait State: Copy {
-    fn set_state_space(&mut self, space: &StateSpace<StateT = Self>);
-    fn get_state_space(&self) -> &StateSpace<StateT = Self>;
-    fn new(space: &StateSpace<StateT = Self>) -> State;
-}
-
-/// A trait representing a generic geometric state space. The associated type `StateT` is the state
-/// representation for the state space; it must implement `State`.
-trait StateSpace {
-    type StateT: State;
-    fn distance(&self, a: &Self::StateT, b: &Self::StateT) -> f64;
-    fn interpolate(&self, from: &Self::StateT, to: &Self::StateT, step: f64) -> Self::StateT;
-    fn interpolate_into(
-        &self,
-        from: &Self::StateT,
-        to: &Self::StateT,
-        step: f64,
-        result: &mut Self::StateT,
-    );
+/// A point in a `StateSpace`. Leaf spaces each have their own variant; `CompoundState` aggregates
+/// the states of a `CompoundStateSpace`'s component spaces, in declaration order. Accessing the
+/// wrong variant (e.g. calling `as_so2` on a `RealVector` state) panics, mirroring OMPL's
+/// `state->as<T>()` casts - callers should only ever reach for the variant matching the state
+/// space that produced the state.
+#[derive(Clone, Debug, PartialEq)]
+pub enum State {
+    RealVector(RealVectorState),
+    SO2(SO2State),
+    SO3(SO3State),
+    Compound(CompoundState),
+}
+
+impl State {
+    pub fn as_real_vector(&self) -> &RealVectorState {
+        match self {
+            State::RealVector(s) => s,
+            _ => panic!("state is not a RealVectorState"),
+        }
+    }
+
+    pub fn as_real_vector_mut(&mut self) -> &mut RealVectorState {
+        match self {
+            State::RealVector(s) => s,
+            _ => panic!("state is not a RealVectorState"),
+        }
+    }
+
+    pub fn as_so2(&self) -> &SO2State {
+        match self {
+            State::SO2(s) => s,
+            _ => panic!("state is not an SO2State"),
+        }
+    }
+
+    pub fn as_so2_mut(&mut self) -> &mut SO2State {
+        match self {
+            State::SO2(s) => s,
+            _ => panic!("state is not an SO2State"),
+        }
+    }
+
+    pub fn as_so3(&self) -> &SO3State {
+        match self {
+            State::SO3(s) => s,
+            _ => panic!("state is not an SO3State"),
+        }
+    }
+
+    pub fn as_so3_mut(&mut self) -> &mut SO3State {
+        match self {
+            State::SO3(s) => s,
+            _ => panic!("state is not an SO3State"),
+        }
+    }
+
+    pub fn as_compound(&self) -> &CompoundState {
+        match self {
+            State::Compound(s) => s,
+            _ => panic!("state is not a CompoundState"),
+        }
+    }
+
+    pub fn as_compound_mut(&mut self) -> &mut CompoundState {
+        match self {
+            State::Compound(s) => s,
+            _ => panic!("state is not a CompoundState"),
+        }
+    }
+}
+
+/// An n-dimensional Euclidean state: a plain vector of coordinates.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RealVectorState {
+    pub values: Vec<f64>,
+}
+
+/// A single angle, always kept wrapped to `[-pi, pi]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SO2State {
+    pub angle: f64,
+}
+
+/// A unit quaternion `(w, x, y, z)` representing an orientation in 3D.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SO3State {
+    pub w: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+/// The state of a `CompoundStateSpace`: one sub-state per component, in the order the
+/// components were declared.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompoundState {
+    pub components: Vec<State>,
+}
+
+/// A trait representing a generic geometric state space. Implementations operate on `State`
+/// values produced by `allocate_state`; passing a `State` variant that doesn't match the space
+/// will panic, the same way OMPL's state space implementations assume the caller passes states
+/// allocated from that same space.
+pub trait StateSpace {
+    /// A fresh state of this space with space-specific initial values (e.g. the zero vector or
+    /// the identity rotation). `interpolate`'s default implementation writes into one of these.
+    fn allocate_state(&self) -> State;
+
+    fn distance(&self, a: &State, b: &State) -> f64;
+
+    fn interpolate(&self, from: &State, to: &State, t: f64) -> State {
+        let mut result = self.allocate_state();
+        self.interpolate_into(from, to, t, &mut result);
+        result
+    }
+
+    fn interpolate_into(&self, from: &State, to: &State, t: f64, result: &mut State);
+
     fn get_name(&self) -> &str;
     fn set_name(&mut self, name: String);
-    fn contains<T: StateSpace>(&self, space: &T) -> bool;
-    fn covers<T: StateSpace>(&self, space: &T) -> bool;
+
+    fn contains(&self, space: &dyn StateSpace) -> bool {
+        self.get_name() == space.get_name()
+    }
+
+    fn covers(&self, space: &dyn StateSpace) -> bool {
+        self.contains(space)
+    }
+
     fn set_segment_length(&mut self, step: f64);
     fn get_segment_length(&self) -> f64;
-    fn count_segments_between(&self, a: &Self::StateT, b: &Self::StateT) -> isize;
+
+    fn count_segments_between(&self, a: &State, b: &State) -> usize {
+        (self.distance(a, b) / self.get_segment_length()).ceil() as usize
+    }
+
+    /// Clamps `state` into this space's bounds in place. The default implementation is a no-op,
+    /// which is correct for spaces without bounds (e.g. `SO3StateSpace`).
+    fn enforce_bounds(&self, state: &mut State) {
+        let _ = state;
+    }
+
+    /// Returns whether `state` already lies within this space's bounds.
+    fn satisfies_bounds(&self, state: &State) -> bool {
+        let _ = state;
+        true
+    }
+
+    /// Sets the bounds for spaces that have a `RealVectorBounds` (currently only
+    /// `RealVectorStateSpace`). The default implementation ignores the call.
+    fn set_bounds(&mut self, bounds: RealVectorBounds) {
+        let _ = bounds;
+    }
+
+    /// Returns the bounds for spaces that have a `RealVectorBounds`, or `None` for spaces
+    /// without one.
+    fn get_bounds(&self) -> Option<&RealVectorBounds> {
+        None
+    }
+
+    /// The number of bytes `serialize` writes for any state from this space.
+    fn serialization_length(&self) -> usize;
+
+    /// Writes `state` to `buf` as raw bytes. `buf` must be at least `serialization_length()`
+    /// bytes long.
+    fn serialize(&self, state: &State, buf: &mut [u8]);
+
+    /// Reads a state previously written by `serialize` out of `buf` into `state`. `buf` must be
+    /// at least `serialization_length()` bytes long.
+    fn deserialize(&self, buf: &[u8], state: &mut State);
+
+    /// Draws a state uniformly at random from this space (within its bounds, where it has any).
+    fn sample_uniform(&self) -> State;
+
+    /// Registers a named projection. The first projection ever registered becomes the default.
+    fn register_projection(&mut self, name: String, projection: Rc<dyn ProjectionEvaluator>);
+
+    fn get_projection(&self, name: &str) -> Option<&Rc<dyn ProjectionEvaluator>>;
+
+    fn get_default_projection(&self) -> Option<&Rc<dyn ProjectionEvaluator>>;
+
+    /// Samples a batch of random state pairs/triples from this space and checks the invariants
+    /// selected by `flags`, following OMPL's `StateSpace::sanityChecks`. Returns the first
+    /// invariant that fails, identifying which check and with what values.
+    fn sanity_checks(&self, flags: SanityFlags) -> Result<(), SanityError> {
+        const SAMPLES: usize = 50;
+        const EPSILON: f64 = 1e-6;
+
+        for _ in 0..SAMPLES {
+            let a = self.sample_uniform();
+            let b = self.sample_uniform();
+            let c = self.sample_uniform();
+
+            if flags.contains(SanityFlags::DISTANCE_DIFFERENT_STATES)
+                && a != b
+                && self.distance(&a, &b) <= 0.0
+            {
+                return Err(SanityError::DistanceNotPositiveForDistinctStates);
+            }
+
+            if flags.contains(SanityFlags::DISTANCE_SYMMETRIC) {
+                let forward = self.distance(&a, &b);
+                let backward = self.distance(&b, &a);
+                if (forward - backward).abs() > EPSILON {
+                    return Err(SanityError::DistanceNotSymmetric { forward, backward });
+                }
+            }
+
+            if flags.contains(SanityFlags::DISTANCE_BOUND) {
+                let distance = self.distance(&a, &b);
+                let bound = self.count_segments_between(&a, &b) as f64 * self.get_segment_length();
+                if distance > bound + EPSILON {
+                    return Err(SanityError::DistanceExceedsSegmentBound { distance, bound });
+                }
+            }
+
+            if flags.contains(SanityFlags::INTERPOLATION) {
+                let start = self.interpolate(&a, &b, 0.0);
+                let end = self.interpolate(&a, &b, 1.0);
+                if self.distance(&a, &start) > EPSILON || self.distance(&b, &end) > EPSILON {
+                    return Err(SanityError::InterpolationEndpointMismatch);
+                }
+
+                let total = self.distance(&a, &b);
+                for step in 1..10 {
+                    let t = step as f64 / 10.0;
+                    let midpoint = self.interpolate(&a, &b, t);
+                    let expected = t * total;
+                    let actual = self.distance(&a, &midpoint);
+                    if (actual - expected).abs() > EPSILON.max(total * 1e-3) {
+                        return Err(SanityError::InterpolationDistanceMismatch { expected, actual });
+                    }
+                }
+            }
+
+            if flags.contains(SanityFlags::TRIANGLE_INEQUALITY) {
+                let direct = self.distance(&a, &c);
+                let via_intermediate = self.distance(&a, &b) + self.distance(&b, &c);
+                if direct > via_intermediate + EPSILON {
+                    return Err(SanityError::TriangleInequalityViolated { direct, via_intermediate });
+                }
+            }
+
+            if flags.contains(SanityFlags::RESPECT_BOUNDS) {
+                if !self.satisfies_bounds(&a) {
+                    return Err(SanityError::SampledStateOutOfBounds);
+                }
+                let mut enforced = a.clone();
+                self.enforce_bounds(&mut enforced);
+                if !self.satisfies_bounds(&enforced) {
+                    return Err(SanityError::SampledStateOutOfBounds);
+                }
+            }
+
+            if flags.contains(SanityFlags::ENFORCE_BOUNDS_NO_OP) && self.satisfies_bounds(&a) {
+                let mut enforced = a.clone();
+                self.enforce_bounds(&mut enforced);
+                if enforced != a {
+                    return Err(SanityError::EnforceBoundsNotNoOp);
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
-#[derive(Copy)]
-struct CompoundState<'a> {
-    values: Vec<Box<State>>,
-    space: &'a StateSpace<StateT = Self>,
+/// The lower/upper bounds of a `RealVectorStateSpace`, one pair per dimension.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RealVectorBounds {
+    pub low: Vec<f64>,
+    pub high: Vec<f64>,
 }
 
-impl State for CompoundState<'a> {
-    fn new(space: &'a StateSpace<StateT = Self>) -> Self {
+impl RealVectorBounds {
+    pub fn new(dimension: usize) -> Self {
         Self {
-            values: Vec::new(),
-            space: space,
+            low: vec![0.0; dimension],
+            high: vec![0.0; dimension],
         }
     }
 
-    fn set_state_space(&mut self, space: &'a StateSpace<StateT = Self>) {
-        self.space = space;
+    /// Sets the same `[low, high]` bound on every dimension.
+    pub fn set_uniform(&mut self, low: f64, high: f64) {
+        self.low.iter_mut().for_each(|v| *v = low);
+        self.high.iter_mut().for_each(|v| *v = high);
     }
 
-    fn get_state_space(&self) -> &'a StateSpace<StateT = Self> {
-        self.space
+    pub fn contains(&self, values: &[f64]) -> bool {
+        values
+            .iter()
+            .zip(&self.low)
+            .zip(&self.high)
+            .all(|((v, low), high)| *v >= *low && *v <= *high)
+    }
+
+    pub fn clamp(&self, values: &mut [f64]) {
+        for ((v, low), high) in values.iter_mut().zip(&self.low).zip(&self.high) {
+            if *v < *low {
+                *v = *low;
+            } else if *v > *high {
+                *v = *high;
+            }
+        }
     }
 }
 
-struct CompoundStateSpace {
+/// An n-dimensional Euclidean state space with L2 distance.
+pub struct RealVectorStateSpace {
     name: String,
-    components: Vec<Box<StateSpace>>,
+    dimension: usize,
     segment_length: f64,
+    bounds: RealVectorBounds,
+    projections: ProjectionRegistry,
 }
 
-impl StateSpace for CompoundStateSpace {
-    type StateT = CompoundState;
-    fn distance(&self, a: &Self::StateT, b: &Self::StateT) -> f64 {
-        multizip((&self.components, &a.values, &b.values))
-            .fold(0.0, |acc, (&subspace, &a_sub, &b_sub)| {
-                acc + subspace.distance(a_sub, b_sub)
-            })
+impl RealVectorStateSpace {
+    pub fn new(name: impl Into<String>, dimension: usize) -> Self {
+        let bounds = RealVectorBounds::new(dimension);
+        let mut projections = ProjectionRegistry::new();
+        projections.register(
+            "default".to_string(),
+            Rc::new(default_real_vector_projection(dimension, &bounds)),
+        );
+
+        Self {
+            name: name.into(),
+            dimension,
+            segment_length: 1.0,
+            bounds,
+            projections,
+        }
     }
 
-    fn interpolate(&self, from: &Self::StateT, to: &Self::StateT, step: f64) -> Self::StateT {
-        let mut result = Self::StateT::new();
-        self.interpolate_into(from, to, step, &mut result);
-        result
+    pub fn dimension(&self) -> usize {
+        self.dimension
+    }
+}
+
+impl StateSpace for RealVectorStateSpace {
+    fn allocate_state(&self) -> State {
+        State::RealVector(RealVectorState {
+            values: vec![0.0; self.dimension],
+        })
+    }
+
+    fn distance(&self, a: &State, b: &State) -> f64 {
+        let (a, b) = (a.as_real_vector(), b.as_real_vector());
+        a.values
+            .iter()
+            .zip(&b.values)
+            .map(|(x, y)| (x - y).powi(2))
+            .sum::<f64>()
+            .sqrt()
     }
 
-    fn interpolate_into(
-        &self,
-        from: &Self::StateT,
-        to: &Self::StateT,
-        step: f64,
-        result: &mut Self::StateT,
-    ) {
+    fn interpolate_into(&self, from: &State, to: &State, t: f64, result: &mut State) {
+        let (from, to) = (from.as_real_vector(), to.as_real_vector());
+        let result = result.as_real_vector_mut();
+        for i in 0..self.dimension {
+            result.values[i] = from.values[i] + (to.values[i] - from.values[i]) * t;
+        }
     }
 
     fn get_name(&self) -> &str {
@@ -94,21 +584,506 @@ impl StateSpace for CompoundStateSpace {
     }
 
     fn set_name(&mut self, name: String) {
-        self.name = name
+        self.name = name;
     }
 
-    fn contains<T: StateSpace>(&self, space: &T) -> bool {
-        // NOTE: This does not attempt to make  the state space by combinations of subspaces - this
-        // seems infeasible to do, but I should check what OMPL does here
-        self.components
-            .iter()
-            .any(|&subspace| subspace.contains(space))
+    fn set_segment_length(&mut self, step: f64) {
+        self.segment_length = step;
     }
 
-    fn covers<T: StateSpace>(&self, space: &T) -> bool {
-        self.components
+    fn get_segment_length(&self) -> f64 {
+        self.segment_length
+    }
+
+    fn enforce_bounds(&self, state: &mut State) {
+        self.bounds.clamp(&mut state.as_real_vector_mut().values);
+    }
+
+    fn satisfies_bounds(&self, state: &State) -> bool {
+        self.bounds.contains(&state.as_real_vector().values)
+    }
+
+    fn set_bounds(&mut self, bounds: RealVectorBounds) {
+        self.bounds = bounds;
+        // the default projection's cell sizes are derived from the bounds - rebuild it so it
+        // doesn't silently keep using the cell sizes computed before bounds were set
+        self.projections.register(
+            "default".to_string(),
+            Rc::new(default_real_vector_projection(self.dimension, &self.bounds)),
+        );
+    }
+
+    fn get_bounds(&self) -> Option<&RealVectorBounds> {
+        Some(&self.bounds)
+    }
+
+    fn serialization_length(&self) -> usize {
+        self.dimension * 8
+    }
+
+    fn serialize(&self, state: &State, buf: &mut [u8]) {
+        for (value, chunk) in state.as_real_vector().values.iter().zip(buf.chunks_exact_mut(8)) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    fn deserialize(&self, buf: &[u8], state: &mut State) {
+        for (value, chunk) in state.as_real_vector_mut().values.iter_mut().zip(buf.chunks_exact(8)) {
+            *value = f64::from_le_bytes(chunk.try_into().unwrap());
+        }
+    }
+
+    fn sample_uniform(&self) -> State {
+        let mut rng = rand::thread_rng();
+        let values = self
+            .bounds
+            .low
             .iter()
-            .any(|&subspace| subspace.contains(space))
+            .zip(&self.bounds.high)
+            .map(|(low, high)| rng.gen_range(*low..=*high))
+            .collect();
+        State::RealVector(RealVectorState { values })
+    }
+
+    fn register_projection(&mut self, name: String, projection: Rc<dyn ProjectionEvaluator>) {
+        self.projections.register(name, projection);
+    }
+
+    fn get_projection(&self, name: &str) -> Option<&Rc<dyn ProjectionEvaluator>> {
+        self.projections.get(name)
+    }
+
+    fn get_default_projection(&self) -> Option<&Rc<dyn ProjectionEvaluator>> {
+        self.projections.get_default()
+    }
+}
+
+/// Wraps `angle` into `[-pi, pi]`.
+fn wrap_angle(angle: f64) -> f64 {
+    let two_pi = 2.0 * PI;
+    let wrapped = (angle + PI).rem_euclid(two_pi);
+    wrapped - PI
+}
+
+/// The space of planar rotations, represented as an angle wrapped to `[-pi, pi]`. Distance is
+/// the shortest angular difference between two angles.
+pub struct SO2StateSpace {
+    name: String,
+    segment_length: f64,
+    projections: ProjectionRegistry,
+}
+
+impl SO2StateSpace {
+    pub fn new(name: impl Into<String>) -> Self {
+        let mut projections = ProjectionRegistry::new();
+        projections.register("default".to_string(), Rc::new(SO2Projection::new()));
+
+        Self {
+            name: name.into(),
+            segment_length: 1.0,
+            projections,
+        }
+    }
+}
+
+/// `SO2StateSpace`'s default projection: the angle itself, already a single dimension.
+struct SO2Projection {
+    cell_size: [f64; 1],
+}
+
+impl SO2Projection {
+    fn new() -> Self {
+        // PI / 10 gives 20 cells around the full circle, a reasonable default grid resolution
+        Self { cell_size: [PI / 10.0] }
+    }
+}
+
+impl ProjectionEvaluator for SO2Projection {
+    fn dimension(&self) -> usize {
+        1
+    }
+
+    fn project(&self, state: &State, coord: &mut [f64]) {
+        coord[0] = state.as_so2().angle;
+    }
+
+    fn cell_sizes(&self) -> &[f64] {
+        &self.cell_size
+    }
+}
+
+impl StateSpace for SO2StateSpace {
+    fn allocate_state(&self) -> State {
+        State::SO2(SO2State { angle: 0.0 })
+    }
+
+    fn distance(&self, a: &State, b: &State) -> f64 {
+        let (a, b) = (a.as_so2(), b.as_so2());
+        let diff = (b.angle - a.angle).abs() % (2.0 * PI);
+        if diff > PI {
+            2.0 * PI - diff
+        } else {
+            diff
+        }
+    }
+
+    fn interpolate_into(&self, from: &State, to: &State, t: f64, result: &mut State) {
+        let (from, to) = (from.as_so2(), to.as_so2());
+        let mut diff = to.angle - from.angle;
+        if diff > PI {
+            diff -= 2.0 * PI;
+        } else if diff < -PI {
+            diff += 2.0 * PI;
+        }
+        result.as_so2_mut().angle = wrap_angle(from.angle + diff * t);
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    fn set_segment_length(&mut self, step: f64) {
+        self.segment_length = step;
+    }
+
+    fn get_segment_length(&self) -> f64 {
+        self.segment_length
+    }
+
+    fn enforce_bounds(&self, state: &mut State) {
+        let state = state.as_so2_mut();
+        state.angle = wrap_angle(state.angle);
+    }
+
+    fn satisfies_bounds(&self, state: &State) -> bool {
+        let angle = state.as_so2().angle;
+        angle >= -PI && angle <= PI
+    }
+
+    fn serialization_length(&self) -> usize {
+        8
+    }
+
+    fn serialize(&self, state: &State, buf: &mut [u8]) {
+        buf[..8].copy_from_slice(&state.as_so2().angle.to_le_bytes());
+    }
+
+    fn deserialize(&self, buf: &[u8], state: &mut State) {
+        state.as_so2_mut().angle = f64::from_le_bytes(buf[..8].try_into().unwrap());
+    }
+
+    fn sample_uniform(&self) -> State {
+        let angle = rand::thread_rng().gen_range(-PI..=PI);
+        State::SO2(SO2State { angle })
+    }
+
+    fn register_projection(&mut self, name: String, projection: Rc<dyn ProjectionEvaluator>) {
+        self.projections.register(name, projection);
+    }
+
+    fn get_projection(&self, name: &str) -> Option<&Rc<dyn ProjectionEvaluator>> {
+        self.projections.get(name)
+    }
+
+    fn get_default_projection(&self) -> Option<&Rc<dyn ProjectionEvaluator>> {
+        self.projections.get_default()
+    }
+}
+
+/// The space of 3D rotations, represented as unit quaternions. Distance is the geodesic distance
+/// `acos(|q1 . q2|)`; interpolation is slerp along the shorter arc.
+pub struct SO3StateSpace {
+    name: String,
+    segment_length: f64,
+    projections: ProjectionRegistry,
+}
+
+impl SO3StateSpace {
+    pub fn new(name: impl Into<String>) -> Self {
+        let mut projections = ProjectionRegistry::new();
+        projections.register("default".to_string(), Rc::new(SO3Projection::new()));
+
+        Self {
+            name: name.into(),
+            segment_length: 1.0,
+            projections,
+        }
+    }
+}
+
+/// `SO3StateSpace`'s default projection: the quaternion's `(x, y, z)` components, dropping `w`
+/// (which is determined by the other three up to sign for a unit quaternion). Crude, but enough
+/// for a KPIECE-style planner to estimate coverage without a space-specific projection.
+struct SO3Projection {
+    cell_sizes: [f64; 3],
+}
+
+impl SO3Projection {
+    fn new() -> Self {
+        Self { cell_sizes: [0.1, 0.1, 0.1] }
+    }
+}
+
+impl ProjectionEvaluator for SO3Projection {
+    fn dimension(&self) -> usize {
+        3
+    }
+
+    fn project(&self, state: &State, coord: &mut [f64]) {
+        let state = state.as_so3();
+        coord[0] = state.x;
+        coord[1] = state.y;
+        coord[2] = state.z;
+    }
+
+    fn cell_sizes(&self) -> &[f64] {
+        &self.cell_sizes
+    }
+}
+
+impl StateSpace for SO3StateSpace {
+    fn allocate_state(&self) -> State {
+        State::SO3(SO3State {
+            w: 1.0,
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        })
+    }
+
+    fn distance(&self, a: &State, b: &State) -> f64 {
+        let (a, b) = (a.as_so3(), b.as_so3());
+        let dot = a.w * b.w + a.x * b.x + a.y * b.y + a.z * b.z;
+        dot.abs().min(1.0).acos()
+    }
+
+    fn interpolate_into(&self, from: &State, to: &State, t: f64, result: &mut State) {
+        let (from, to) = (from.as_so3(), to.as_so3());
+        let mut dot = from.w * to.w + from.x * to.x + from.y * to.y + from.z * to.z;
+
+        // slerp always takes the shorter of the two arcs between the quaternions
+        let to = if dot < 0.0 {
+            dot = -dot;
+            SO3State {
+                w: -to.w,
+                x: -to.x,
+                y: -to.y,
+                z: -to.z,
+            }
+        } else {
+            to.clone()
+        };
+
+        let (s_from, s_to) = if dot > 0.9995 {
+            // the quaternions are nearly identical; linear interpolation avoids a division by
+            // (near) zero below and is indistinguishable from slerp at this distance
+            (1.0 - t, t)
+        } else {
+            let theta_0 = dot.acos();
+            let theta = theta_0 * t;
+            let sin_theta_0 = theta_0.sin();
+            ((theta_0 - theta).sin() / sin_theta_0, theta.sin() / sin_theta_0)
+        };
+
+        let result = result.as_so3_mut();
+        result.w = s_from * from.w + s_to * to.w;
+        result.x = s_from * from.x + s_to * to.x;
+        result.y = s_from * from.y + s_to * to.y;
+        result.z = s_from * from.z + s_to * to.z;
+        let norm = (result.w.powi(2) + result.x.powi(2) + result.y.powi(2) + result.z.powi(2)).sqrt();
+        result.w /= norm;
+        result.x /= norm;
+        result.y /= norm;
+        result.z /= norm;
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    fn set_segment_length(&mut self, step: f64) {
+        self.segment_length = step;
+    }
+
+    fn get_segment_length(&self) -> f64 {
+        self.segment_length
+    }
+
+    fn serialization_length(&self) -> usize {
+        32
+    }
+
+    fn serialize(&self, state: &State, buf: &mut [u8]) {
+        let state = state.as_so3();
+        buf[0..8].copy_from_slice(&state.w.to_le_bytes());
+        buf[8..16].copy_from_slice(&state.x.to_le_bytes());
+        buf[16..24].copy_from_slice(&state.y.to_le_bytes());
+        buf[24..32].copy_from_slice(&state.z.to_le_bytes());
+    }
+
+    fn deserialize(&self, buf: &[u8], state: &mut State) {
+        let state = state.as_so3_mut();
+        state.w = f64::from_le_bytes(buf[0..8].try_into().unwrap());
+        state.x = f64::from_le_bytes(buf[8..16].try_into().unwrap());
+        state.y = f64::from_le_bytes(buf[16..24].try_into().unwrap());
+        state.z = f64::from_le_bytes(buf[24..32].try_into().unwrap());
+    }
+
+    fn sample_uniform(&self) -> State {
+        // Shoemake's method for a uniformly-distributed random unit quaternion
+        let mut rng = rand::thread_rng();
+        let u1: f64 = rng.gen();
+        let u2: f64 = rng.gen();
+        let u3: f64 = rng.gen();
+        let r1 = (1.0 - u1).sqrt();
+        let r2 = u1.sqrt();
+        State::SO3(SO3State {
+            w: r1 * (2.0 * PI * u2).sin(),
+            x: r1 * (2.0 * PI * u2).cos(),
+            y: r2 * (2.0 * PI * u3).sin(),
+            z: r2 * (2.0 * PI * u3).cos(),
+        })
+    }
+
+    fn register_projection(&mut self, name: String, projection: Rc<dyn ProjectionEvaluator>) {
+        self.projections.register(name, projection);
+    }
+
+    fn get_projection(&self, name: &str) -> Option<&Rc<dyn ProjectionEvaluator>> {
+        self.projections.get(name)
+    }
+
+    fn get_default_projection(&self) -> Option<&Rc<dyn ProjectionEvaluator>> {
+        self.projections.get_default()
+    }
+}
+
+/// A state space built by combining other state spaces. Each component carries a weight, so
+/// `distance` is the weighted sum of the components' distances; `SE2`/`SE3` are the common case
+/// of a `RealVectorStateSpace` combined with an `SO2`/`SO3StateSpace`.
+pub struct CompoundStateSpace {
+    name: String,
+    components: Vec<(Box<dyn StateSpace>, f64)>,
+    segment_length: f64,
+    projections: ProjectionRegistry,
+}
+
+impl CompoundStateSpace {
+    pub fn new(name: impl Into<String>, components: Vec<(Box<dyn StateSpace>, f64)>) -> Self {
+        let mut projections = ProjectionRegistry::new();
+        if let Some(default) = Self::default_projection(&components) {
+            projections.register("default".to_string(), Rc::new(default));
+        }
+
+        Self {
+            name: name.into(),
+            components,
+            segment_length: 1.0,
+            projections,
+        }
+    }
+
+    /// Concatenates each component's own default projection, in declaration order; components
+    /// without one are skipped. See `concatenate_default_projections`.
+    fn default_projection(components: &[(Box<dyn StateSpace>, f64)]) -> Option<CompoundProjection> {
+        concatenate_default_projections(components.iter().map(|(space, _weight)| space.get_default_projection()))
+    }
+
+    pub fn components(&self) -> &[(Box<dyn StateSpace>, f64)] {
+        &self.components
+    }
+
+    /// Mutable access to the component spaces, e.g. to call `set_bounds` on one of them after
+    /// the `CompoundStateSpace` has already been built (`se2`/`se3` set their position
+    /// component's bounds from `position_bounds`; this replaces them).
+    pub fn components_mut(&mut self) -> &mut [(Box<dyn StateSpace>, f64)] {
+        &mut self.components
+    }
+
+    /// The byte offset of each component's serialized state within the buffer produced by
+    /// `serialize`, in declaration order.
+    fn component_offsets(&self) -> Vec<usize> {
+        let mut offsets = Vec::with_capacity(self.components.len());
+        let mut offset = 0;
+        for (space, _weight) in &self.components {
+            offsets.push(offset);
+            offset += space.serialization_length();
+        }
+        offsets
+    }
+
+    /// The planar pose space: a 2D position bounded by `position_bounds`, combined with an
+    /// `SO2StateSpace` orientation weighted the way OMPL's `SE2StateSpace` weights its rotation
+    /// component.
+    pub fn se2(name: impl Into<String>, position_bounds: RealVectorBounds) -> Self {
+        let mut position = RealVectorStateSpace::new("position", 2);
+        position.set_bounds(position_bounds);
+        Self::new(
+            name,
+            vec![
+                (Box::new(position) as Box<dyn StateSpace>, 1.0),
+                (Box::new(SO2StateSpace::new("rotation")) as Box<dyn StateSpace>, 0.5),
+            ],
+        )
+    }
+
+    /// The rigid-body pose space: a 3D position bounded by `position_bounds`, combined with an
+    /// `SO3StateSpace` orientation.
+    pub fn se3(name: impl Into<String>, position_bounds: RealVectorBounds) -> Self {
+        let mut position = RealVectorStateSpace::new("position", 3);
+        position.set_bounds(position_bounds);
+        Self::new(
+            name,
+            vec![
+                (Box::new(position) as Box<dyn StateSpace>, 1.0),
+                (Box::new(SO3StateSpace::new("rotation")) as Box<dyn StateSpace>, 0.5),
+            ],
+        )
+    }
+}
+
+impl StateSpace for CompoundStateSpace {
+    fn allocate_state(&self) -> State {
+        State::Compound(CompoundState {
+            components: self.components.iter().map(|(space, _)| space.allocate_state()).collect(),
+        })
+    }
+
+    fn distance(&self, a: &State, b: &State) -> f64 {
+        let (a, b) = (a.as_compound(), b.as_compound());
+        multizip((&self.components, &a.components, &b.components)).fold(
+            0.0,
+            |acc, ((space, weight), a_sub, b_sub)| acc + weight * space.distance(a_sub, b_sub),
+        )
+    }
+
+    fn interpolate_into(&self, from: &State, to: &State, t: f64, result: &mut State) {
+        let from = &from.as_compound().components;
+        let to = &to.as_compound().components;
+        let result = &mut result.as_compound_mut().components;
+        for (i, (space, _weight)) in self.components.iter().enumerate() {
+            space.interpolate_into(&from[i], &to[i], t, &mut result[i]);
+        }
+    }
+
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    fn contains(&self, space: &dyn StateSpace) -> bool {
+        self.get_name() == space.get_name() || self.components.iter().any(|(sub, _)| sub.contains(space))
     }
 
     fn set_segment_length(&mut self, step: f64) {
@@ -119,5 +1094,210 @@ impl StateSpace for CompoundStateSpace {
         self.segment_length
     }
 
-    fn count_segments_between(&self, a: &Self::StateT, b: &Self::StateT) -> isize {}
+    fn enforce_bounds(&self, state: &mut State) {
+        let state = &mut state.as_compound_mut().components;
+        for (i, (space, _weight)) in self.components.iter().enumerate() {
+            space.enforce_bounds(&mut state[i]);
+        }
+    }
+
+    fn satisfies_bounds(&self, state: &State) -> bool {
+        let state = &state.as_compound().components;
+        self.components
+            .iter()
+            .enumerate()
+            .all(|(i, (space, _weight))| space.satisfies_bounds(&state[i]))
+    }
+
+    fn serialization_length(&self) -> usize {
+        self.components.iter().map(|(space, _weight)| space.serialization_length()).sum()
+    }
+
+    fn serialize(&self, state: &State, buf: &mut [u8]) {
+        let state = &state.as_compound().components;
+        let offsets = self.component_offsets();
+        for (i, (space, _weight)) in self.components.iter().enumerate() {
+            let end = offsets[i] + space.serialization_length();
+            space.serialize(&state[i], &mut buf[offsets[i]..end]);
+        }
+    }
+
+    fn deserialize(&self, buf: &[u8], state: &mut State) {
+        let state = &mut state.as_compound_mut().components;
+        let offsets = self.component_offsets();
+        for (i, (space, _weight)) in self.components.iter().enumerate() {
+            let end = offsets[i] + space.serialization_length();
+            space.deserialize(&buf[offsets[i]..end], &mut state[i]);
+        }
+    }
+
+    fn sample_uniform(&self) -> State {
+        State::Compound(CompoundState {
+            components: self.components.iter().map(|(space, _weight)| space.sample_uniform()).collect(),
+        })
+    }
+
+    fn register_projection(&mut self, name: String, projection: Rc<dyn ProjectionEvaluator>) {
+        self.projections.register(name, projection);
+    }
+
+    fn get_projection(&self, name: &str) -> Option<&Rc<dyn ProjectionEvaluator>> {
+        self.projections.get(name)
+    }
+
+    fn get_default_projection(&self) -> Option<&Rc<dyn ProjectionEvaluator>> {
+        self.projections.get_default()
+    }
+}
+
+/// A `StateSpace` whose native state is exactly one `State` variant. `rpl_derive`'s
+/// `#[derive(CompoundStateSpace)]` uses this to go from a sub-space's type to its state type
+/// without hardcoding the mapping, so the state struct it generates can expose `pub` fields of
+/// the right concrete type (e.g. `RealVectorState`) instead of a `State` that still needs
+/// unwrapping.
+pub trait LeafStateSpace: StateSpace {
+    type State;
+    fn wrap(state: Self::State) -> State;
+    fn unwrap_ref(state: &State) -> &Self::State;
+    fn unwrap_mut(state: &mut State) -> &mut Self::State;
+}
+
+impl LeafStateSpace for RealVectorStateSpace {
+    type State = RealVectorState;
+
+    fn wrap(state: Self::State) -> State {
+        State::RealVector(state)
+    }
+
+    fn unwrap_ref(state: &State) -> &Self::State {
+        state.as_real_vector()
+    }
+
+    fn unwrap_mut(state: &mut State) -> &mut Self::State {
+        state.as_real_vector_mut()
+    }
+}
+
+impl LeafStateSpace for SO2StateSpace {
+    type State = SO2State;
+
+    fn wrap(state: Self::State) -> State {
+        State::SO2(state)
+    }
+
+    fn unwrap_ref(state: &State) -> &Self::State {
+        state.as_so2()
+    }
+
+    fn unwrap_mut(state: &mut State) -> &mut Self::State {
+        state.as_so2_mut()
+    }
+}
+
+impl LeafStateSpace for SO3StateSpace {
+    type State = SO3State;
+
+    fn wrap(state: Self::State) -> State {
+        State::SO3(state)
+    }
+
+    fn unwrap_ref(state: &State) -> &Self::State {
+        state.as_so3()
+    }
+
+    fn unwrap_mut(state: &mut State) -> &mut Self::State {
+        state.as_so3_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounded_real_vector(dimension: usize) -> RealVectorStateSpace {
+        let mut space = RealVectorStateSpace::new("real", dimension);
+        let mut bounds = RealVectorBounds::new(dimension);
+        bounds.set_uniform(-1.0, 1.0);
+        space.set_bounds(bounds);
+        space
+    }
+
+    fn uniform_bounds(dimension: usize) -> RealVectorBounds {
+        let mut bounds = RealVectorBounds::new(dimension);
+        bounds.set_uniform(-1.0, 1.0);
+        bounds
+    }
+
+    fn round_trip(space: &dyn StateSpace, state: &State) -> State {
+        let mut buf = vec![0u8; space.serialization_length()];
+        space.serialize(state, &mut buf);
+        let mut decoded = space.allocate_state();
+        space.deserialize(&buf, &mut decoded);
+        decoded
+    }
+
+    #[test]
+    fn real_vector_serialization_round_trips() {
+        let space = bounded_real_vector(3);
+        let state = space.sample_uniform();
+        assert_eq!(round_trip(&space, &state), state);
+    }
+
+    #[test]
+    fn so2_serialization_round_trips() {
+        let space = SO2StateSpace::new("so2");
+        let state = space.sample_uniform();
+        assert_eq!(round_trip(&space, &state), state);
+    }
+
+    #[test]
+    fn so3_serialization_round_trips() {
+        let space = SO3StateSpace::new("so3");
+        let state = space.sample_uniform();
+        assert_eq!(round_trip(&space, &state), state);
+    }
+
+    #[test]
+    fn se2_serialization_round_trips() {
+        let space = CompoundStateSpace::se2("se2", uniform_bounds(2));
+        let state = space.sample_uniform();
+        assert_eq!(round_trip(&space, &state), state);
+    }
+
+    #[test]
+    fn se3_serialization_round_trips() {
+        let space = CompoundStateSpace::se3("se3", uniform_bounds(3));
+        let state = space.sample_uniform();
+        assert_eq!(round_trip(&space, &state), state);
+    }
+
+    #[test]
+    fn real_vector_passes_sanity_checks() {
+        let space = bounded_real_vector(3);
+        assert_eq!(space.sanity_checks(SanityFlags::all()), Ok(()));
+    }
+
+    #[test]
+    fn so2_passes_sanity_checks() {
+        let space = SO2StateSpace::new("so2");
+        assert_eq!(space.sanity_checks(SanityFlags::all()), Ok(()));
+    }
+
+    #[test]
+    fn so3_passes_sanity_checks() {
+        let space = SO3StateSpace::new("so3");
+        assert_eq!(space.sanity_checks(SanityFlags::all()), Ok(()));
+    }
+
+    #[test]
+    fn se2_passes_sanity_checks() {
+        let space = CompoundStateSpace::se2("se2", uniform_bounds(2));
+        assert_eq!(space.sanity_checks(SanityFlags::all()), Ok(()));
+    }
+
+    #[test]
+    fn se3_passes_sanity_checks() {
+        let space = CompoundStateSpace::se3("se3", uniform_bounds(3));
+        assert_eq!(space.sanity_checks(SanityFlags::all()), Ok(()));
+    }
 }
@@ -0,0 +1,249 @@
+//! Proc-macro companion crate for `rpl`.
+//!
+//! `#[derive(CompoundStateSpace)]` turns a struct of sub-spaces into a `StateSpace` impl (and a
+//! matching, typed state struct) without writing the per-field `distance`/`interpolate_into`
+//! boilerplate by hand.
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Index};
+
+/// See the crate docs. Each field's type must implement `rpl`'s `LeafStateSpace` (`rpl`'s own
+/// `RealVectorStateSpace`, `SO2StateSpace`, and `SO3StateSpace` all do). A field's weight in the
+/// compound distance defaults to `1.0`; override it with `#[weight(0.5)]`.
+#[proc_macro_derive(CompoundStateSpace, attributes(weight))]
+pub fn derive_compound_state_space(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let parts_name = &input.ident;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => panic!("#[derive(CompoundStateSpace)] requires a struct with named fields, one per sub-space"),
+        },
+        _ => panic!("#[derive(CompoundStateSpace)] can only be derived for structs"),
+    };
+
+    let field_ident: Vec<_> = fields.iter().map(|f| f.ident.clone().unwrap()).collect();
+    let field_index: Vec<_> = (0..field_ident.len()).map(Index::from).collect();
+    let field_ty: Vec<_> = fields.iter().map(|f| f.ty.clone()).collect();
+    let field_weight: Vec<_> = fields.iter().map(field_weight).collect();
+
+    let space_name = format_ident!("{}Space", parts_name);
+    let state_name = format_ident!("{}State", parts_name);
+
+    let expanded = quote! {
+        /// The typed state of a space generated by `#[derive(CompoundStateSpace)]`, with one
+        /// named, concretely-typed field per sub-space - no downcasting a `State` enum variant
+        /// required to read a component.
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct #state_name {
+            #(pub #field_ident: <#field_ty as ::rpl::types::geometric::spaces::LeafStateSpace>::State,)*
+        }
+
+        impl #state_name {
+            pub fn from_state(state: &::rpl::types::geometric::spaces::State) -> Self {
+                let components = &state.as_compound().components;
+                Self {
+                    #(#field_ident: <#field_ty as ::rpl::types::geometric::spaces::LeafStateSpace>::unwrap_ref(
+                        &components[#field_index],
+                    ).clone(),)*
+                }
+            }
+
+            pub fn into_state(self) -> ::rpl::types::geometric::spaces::State {
+                ::rpl::types::geometric::spaces::State::Compound(::rpl::types::geometric::spaces::CompoundState {
+                    components: vec![
+                        #(<#field_ty as ::rpl::types::geometric::spaces::LeafStateSpace>::wrap(self.#field_ident),)*
+                    ],
+                })
+            }
+        }
+
+        /// A `StateSpace` built from the deriving struct's fields, generated by
+        /// `#[derive(CompoundStateSpace)]`. `distance` is the weighted sum of the fields'
+        /// distances; `interpolate_into` dispatches field-by-field; `contains` recurses into the
+        /// fields the same way `CompoundStateSpace::contains` does. `new` also concatenates each
+        /// field's own default projection into this space's default, same as
+        /// `CompoundStateSpace::new`.
+        pub struct #space_name {
+            pub parts: #parts_name,
+            name: ::std::string::String,
+            segment_length: f64,
+            projections: ::rpl::types::geometric::spaces::ProjectionRegistry,
+        }
+
+        impl #space_name {
+            pub fn new(name: impl Into<::std::string::String>, parts: #parts_name) -> Self {
+                let mut projections = ::rpl::types::geometric::spaces::ProjectionRegistry::new();
+                let default_projection = ::rpl::types::geometric::spaces::concatenate_default_projections(vec![
+                    #(::rpl::types::geometric::spaces::StateSpace::get_default_projection(&parts.#field_ident),)*
+                ]);
+                if let Some(default_projection) = default_projection {
+                    projections.register("default".to_string(), ::std::rc::Rc::new(default_projection));
+                }
+
+                Self {
+                    parts,
+                    name: name.into(),
+                    segment_length: 1.0,
+                    projections,
+                }
+            }
+        }
+
+        impl ::rpl::types::geometric::spaces::StateSpace for #space_name {
+            fn allocate_state(&self) -> ::rpl::types::geometric::spaces::State {
+                ::rpl::types::geometric::spaces::State::Compound(::rpl::types::geometric::spaces::CompoundState {
+                    components: vec![
+                        #(::rpl::types::geometric::spaces::StateSpace::allocate_state(&self.parts.#field_ident),)*
+                    ],
+                })
+            }
+
+            fn distance(&self, a: &::rpl::types::geometric::spaces::State, b: &::rpl::types::geometric::spaces::State) -> f64 {
+                let a = &a.as_compound().components;
+                let b = &b.as_compound().components;
+                0.0 #(+ #field_weight * ::rpl::types::geometric::spaces::StateSpace::distance(
+                    &self.parts.#field_ident,
+                    &a[#field_index],
+                    &b[#field_index],
+                ))*
+            }
+
+            fn interpolate_into(
+                &self,
+                from: &::rpl::types::geometric::spaces::State,
+                to: &::rpl::types::geometric::spaces::State,
+                t: f64,
+                result: &mut ::rpl::types::geometric::spaces::State,
+            ) {
+                let from = &from.as_compound().components;
+                let to = &to.as_compound().components;
+                let result = &mut result.as_compound_mut().components;
+                #(::rpl::types::geometric::spaces::StateSpace::interpolate_into(
+                    &self.parts.#field_ident,
+                    &from[#field_index],
+                    &to[#field_index],
+                    t,
+                    &mut result[#field_index],
+                );)*
+            }
+
+            fn get_name(&self) -> &str {
+                &self.name
+            }
+
+            fn contains(&self, space: &dyn ::rpl::types::geometric::spaces::StateSpace) -> bool {
+                self.get_name() == space.get_name()
+                    #(|| ::rpl::types::geometric::spaces::StateSpace::contains(&self.parts.#field_ident, space))*
+            }
+
+            fn set_name(&mut self, name: ::std::string::String) {
+                self.name = name;
+            }
+
+            fn set_segment_length(&mut self, step: f64) {
+                self.segment_length = step;
+            }
+
+            fn get_segment_length(&self) -> f64 {
+                self.segment_length
+            }
+
+            fn enforce_bounds(&self, state: &mut ::rpl::types::geometric::spaces::State) {
+                let state = &mut state.as_compound_mut().components;
+                #(::rpl::types::geometric::spaces::StateSpace::enforce_bounds(
+                    &self.parts.#field_ident,
+                    &mut state[#field_index],
+                );)*
+            }
+
+            fn satisfies_bounds(&self, state: &::rpl::types::geometric::spaces::State) -> bool {
+                let state = &state.as_compound().components;
+                true #(&& ::rpl::types::geometric::spaces::StateSpace::satisfies_bounds(
+                    &self.parts.#field_ident,
+                    &state[#field_index],
+                ))*
+            }
+
+            fn serialization_length(&self) -> usize {
+                0 #(+ ::rpl::types::geometric::spaces::StateSpace::serialization_length(&self.parts.#field_ident))*
+            }
+
+            fn serialize(&self, state: &::rpl::types::geometric::spaces::State, buf: &mut [u8]) {
+                let state = &state.as_compound().components;
+                let mut offset = 0;
+                #({
+                    let len = ::rpl::types::geometric::spaces::StateSpace::serialization_length(&self.parts.#field_ident);
+                    ::rpl::types::geometric::spaces::StateSpace::serialize(
+                        &self.parts.#field_ident,
+                        &state[#field_index],
+                        &mut buf[offset..offset + len],
+                    );
+                    offset += len;
+                })*
+            }
+
+            fn deserialize(&self, buf: &[u8], state: &mut ::rpl::types::geometric::spaces::State) {
+                let state = &mut state.as_compound_mut().components;
+                let mut offset = 0;
+                #({
+                    let len = ::rpl::types::geometric::spaces::StateSpace::serialization_length(&self.parts.#field_ident);
+                    ::rpl::types::geometric::spaces::StateSpace::deserialize(
+                        &self.parts.#field_ident,
+                        &buf[offset..offset + len],
+                        &mut state[#field_index],
+                    );
+                    offset += len;
+                })*
+            }
+
+            fn sample_uniform(&self) -> ::rpl::types::geometric::spaces::State {
+                ::rpl::types::geometric::spaces::State::Compound(::rpl::types::geometric::spaces::CompoundState {
+                    components: vec![
+                        #(::rpl::types::geometric::spaces::StateSpace::sample_uniform(&self.parts.#field_ident),)*
+                    ],
+                })
+            }
+
+            fn register_projection(
+                &mut self,
+                name: ::std::string::String,
+                projection: ::std::rc::Rc<dyn ::rpl::types::geometric::spaces::ProjectionEvaluator>,
+            ) {
+                self.projections.register(name, projection);
+            }
+
+            fn get_projection(
+                &self,
+                name: &str,
+            ) -> ::std::option::Option<&::std::rc::Rc<dyn ::rpl::types::geometric::spaces::ProjectionEvaluator>> {
+                self.projections.get(name)
+            }
+
+            fn get_default_projection(
+                &self,
+            ) -> ::std::option::Option<&::std::rc::Rc<dyn ::rpl::types::geometric::spaces::ProjectionEvaluator>> {
+                self.projections.get_default()
+            }
+        }
+    };
+
+    expanded.into()
+}
+
+/// Reads a field's `#[weight(..)]` attribute, defaulting to `1.0` when absent.
+fn field_weight(field: &syn::Field) -> f64 {
+    field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("weight"))
+        .map(|attr| {
+            let lit: syn::LitFloat = attr
+                .parse_args()
+                .expect("#[weight(..)] takes a single float literal, e.g. #[weight(0.5)]");
+            lit.base10_parse().unwrap()
+        })
+        .unwrap_or(1.0)
+}
@@ -0,0 +1,102 @@
+use rpl::types::geometric::spaces::{
+    CompoundState, ProjectionEvaluator, RealVectorBounds, RealVectorState, RealVectorStateSpace, SO2State,
+    SO2StateSpace, SanityFlags, State, StateSpace,
+};
+use rpl_derive::CompoundStateSpace;
+
+#[derive(CompoundStateSpace)]
+struct Se2Parts {
+    position: RealVectorStateSpace,
+    #[weight(0.5)]
+    rotation: SO2StateSpace,
+}
+
+fn se2_parts_space() -> Se2PartsSpace {
+    let mut position = RealVectorStateSpace::new("position", 2);
+    let mut bounds = RealVectorBounds::new(2);
+    bounds.set_uniform(-1.0, 1.0);
+    StateSpace::set_bounds(&mut position, bounds);
+
+    Se2PartsSpace::new("se2_parts", Se2Parts { position, rotation: SO2StateSpace::new("rotation") })
+}
+
+fn state_at(position: [f64; 2], angle: f64) -> State {
+    State::Compound(CompoundState {
+        components: vec![
+            State::RealVector(RealVectorState { values: position.to_vec() }),
+            State::SO2(SO2State { angle }),
+        ],
+    })
+}
+
+#[test]
+fn distance_is_the_weighted_sum_of_field_distances() {
+    let space = se2_parts_space();
+    let a = state_at([0.0, 0.0], 0.0);
+    let b = state_at([3.0, 4.0], 0.0);
+
+    // position distance is 5 (3-4-5 triangle) at weight 1.0, rotation distance is 0
+    assert_eq!(space.distance(&a, &b), 5.0);
+}
+
+#[test]
+fn interpolate_into_dispatches_field_by_field() {
+    let space = se2_parts_space();
+    let a = state_at([0.0, 0.0], 0.0);
+    let b = state_at([2.0, 4.0], 0.0);
+
+    let midpoint = space.interpolate(&a, &b, 0.5);
+    let midpoint = Se2PartsState::from_state(&midpoint);
+
+    assert_eq!(midpoint.position.values, vec![1.0, 2.0]);
+    assert_eq!(midpoint.rotation.angle, 0.0);
+}
+
+#[test]
+fn typed_accessors_round_trip_through_the_compound_state() {
+    let typed = Se2PartsState {
+        position: RealVectorState { values: vec![1.0, -2.0] },
+        rotation: SO2State { angle: 0.25 },
+    };
+
+    let round_tripped = Se2PartsState::from_state(&typed.clone().into_state());
+    assert_eq!(round_tripped, typed);
+}
+
+#[test]
+fn serialization_round_trips() {
+    let space = se2_parts_space();
+    let state = space.sample_uniform();
+
+    let mut buf = vec![0u8; space.serialization_length()];
+    space.serialize(&state, &mut buf);
+
+    let mut decoded = space.allocate_state();
+    space.deserialize(&buf, &mut decoded);
+
+    assert_eq!(decoded, state);
+}
+
+#[test]
+fn default_projection_concatenates_field_projections() {
+    let space = se2_parts_space();
+    let projection = space.get_default_projection().expect("position and rotation both have defaults");
+
+    // position's default projects to 2 dimensions (dimension <= 2 is the identity case),
+    // rotation's to 1 (the angle itself)
+    assert_eq!(projection.dimension(), 3);
+}
+
+#[test]
+fn contains_recurses_into_fields() {
+    let space = se2_parts_space();
+    assert!(space.contains(&RealVectorStateSpace::new("position", 2)));
+    assert!(space.contains(&SO2StateSpace::new("rotation")));
+    assert!(!space.contains(&SO2StateSpace::new("unrelated")));
+}
+
+#[test]
+fn passes_sanity_checks() {
+    let space = se2_parts_space();
+    assert_eq!(space.sanity_checks(SanityFlags::all()), Ok(()));
+}